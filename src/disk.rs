@@ -0,0 +1,97 @@
+//! Validates a [`Plan`] against the disk actually available at a given path.
+//!
+//! A [`Plan`] is only an estimate; this module checks it against reality by
+//! querying the filesystem backing a directory (the way AVML's `disk_usage()`
+//! checks free space before writing a memory image), so operators can catch an
+//! undersized data volume before Elasticsearch does.
+
+use crate::planner::Plan;
+use nix::sys::statvfs::statvfs;
+use std::path::Path;
+
+/// Fixed padding, in GB, added on top of the plan's estimate to absorb
+/// filesystem overhead and rounding (reserved blocks, journal, block-size
+/// rounding) that a pure byte-count estimate won't capture.
+const DISK_CHECK_PADDING_GB: f64 = 1.0;
+
+/// Checks a [`Plan`]'s per-node disk estimate against the free space actually
+/// available on the filesystem backing `path`.
+///
+/// Queries `path` via `statvfs` for total and available bytes, adds
+/// [`DISK_CHECK_PADDING_GB`] to `plan.disk_per_node`, and fails if that padded
+/// estimate either exceeds the available space outright or would push the
+/// device's projected usage above `max_usage_pct`.
+///
+/// # Parameters
+///
+/// - `plan` — A previously computed [`plan_capacity`](crate::plan_capacity) result.
+/// - `path` — Any path on the target data volume (the mount point is resolved by
+///   `statvfs`, so a subdirectory works fine).
+/// - `max_usage_pct` — Maximum allowed fraction of the device's total capacity.
+///   If `None`, defaults to `plan.target_utilization`.
+///
+/// # Errors
+///
+/// Returns an [`Err`] string if `path` can't be statted, if the padded estimate
+/// exceeds available free space, or if it would exceed `max_usage_pct` of the
+/// device's total capacity. The message includes both the estimate and the
+/// measured free space so the caller can see why.
+pub fn validate_against_disk(
+    plan: &Plan,
+    path: &Path,
+    max_usage_pct: Option<f64>,
+) -> Result<(), String> {
+    let stats =
+        statvfs(path).map_err(|e| format!("failed to statvfs {}: {}", path.display(), e))?;
+
+    let block_size = stats.fragment_size() as f64;
+    let total_gb = (stats.blocks() as f64 * block_size) / 1e9;
+    let available_gb = (stats.blocks_available() as f64 * block_size) / 1e9;
+
+    let max_pct = max_usage_pct.unwrap_or(plan.target_utilization);
+    let estimate_gb = plan.disk_per_node + DISK_CHECK_PADDING_GB;
+
+    if estimate_gb > available_gb {
+        return Err(format!(
+            "plan needs ~{:.1} GB per node (incl. {:.1} GB padding) but only {:.1} GB is free at {}",
+            estimate_gb,
+            DISK_CHECK_PADDING_GB,
+            available_gb,
+            path.display()
+        ));
+    }
+
+    let projected_usage_pct = estimate_gb / total_gb;
+    if projected_usage_pct > max_pct {
+        return Err(format!(
+            "plan would use {:.0}% of the {:.1} GB device at {} (estimate {:.1} GB), \
+             exceeding the {:.0}% limit",
+            projected_usage_pct * 100.0,
+            total_gb,
+            path.display(),
+            estimate_gb,
+            max_pct * 100.0
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan_capacity;
+
+    #[test]
+    fn accepts_plan_that_fits_on_disk() {
+        let plan = plan_capacity(3, 1, 0, 1.0, None, 0.0, 0.0, Some(0.0), 0.75).unwrap();
+        assert!(validate_against_disk(&plan, &std::env::temp_dir(), None).is_ok());
+    }
+
+    #[test]
+    fn rejects_plan_exceeding_usage_limit() {
+        let plan = plan_capacity(3, 1, 0, 1.0, None, 0.0, 0.0, Some(0.0), 0.75).unwrap();
+        let result = validate_against_disk(&plan, &std::env::temp_dir(), Some(1e-12));
+        assert!(result.is_err());
+    }
+}
@@ -1,6 +1,16 @@
 #![doc = include_str!("../README.md")]
 
+pub mod disk;
+pub mod output;
+pub mod placement;
+
+pub use disk::validate_against_disk;
+pub use output::{render, OutputFormat};
+pub use placement::{plan_placement, PlacementResult};
+pub use planner::{plan_capacity, plan_capacity_nodes, NodeLoad, NodeSpec, Plan, Units};
+
 pub mod planner {
+    use serde::{Deserialize, Serialize};
     use std::fmt::{Display, Formatter, Result as FmtResult};
 
     /// Represents the computed capacity plan for an Elasticsearch cluster.
@@ -8,21 +18,35 @@ pub mod planner {
     /// All values are expressed in **gigabytes (GB, base-10)**.
     /// This struct is returned by the capacity calculation function and
     /// provides both cluster-level and per-node estimates.
-    #[derive(Debug, Clone, Copy)]
+    ///
+    /// Serde-serializable so it can be emitted as JSON (see
+    /// [`output::render`](crate::output::render)) for capacity dashboards and CI gates.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Plan {
-        /// Total data size for all primary and replica shards combined.
+        /// Raw (uncompressed) data size for all primary and replica shards combined.
         ///
         /// Formula: `primaries * shard_size_gb * (1 + replicas)`
-        pub base: f64,
+        pub raw_base: f64,
+
+        /// Steady-state data size after applying `compression_ratio` to `raw_base`.
+        ///
+        /// This is what Elasticsearch actually stores on disk once segments are
+        /// compressed; compression is not guaranteed to shrink bytes (hence
+        /// `compression_ratio` defaulting to `1.0`, not some assumed savings).
+        ///
+        /// Formula: `raw_base * compression_ratio`
+        pub stored_base: f64,
 
-        /// Base size plus Lucene merge overhead.
+        /// The transient high-water mark reached while one shard is being
+        /// force-merged, since Lucene rewrites a single shard at a time rather
+        /// than the whole cluster at once.
         ///
-        /// Formula: `base * (1 + overhead_merge)`
-        pub with_merge: f64,
+        /// Formula: `stored_base + (shard_size_gb * compression_ratio * overhead_merge)`
+        pub peak_merge: f64,
 
         /// Size after applying headroom for watermarks and ingestion bursts.
         ///
-        /// Formula: `with_merge * (1 + headroom)`
+        /// Formula: `peak_merge * (1 + headroom)`
         pub with_headroom: f64,
 
         /// Total relocation/rebalancing buffer for all nodes combined.
@@ -45,6 +69,21 @@ pub mod planner {
         /// Formula: `per_node / target_utilization`
         pub disk_per_node: f64,
 
+        /// Per-node breakdown of assigned load, populated when capacities are known.
+        ///
+        /// Empty for [`plan_capacity`], which has no visibility into individual node
+        /// capacities and can only report a cluster-wide average. Populated by
+        /// [`plan_capacity_nodes`], which knows each node's `capacity_gb` and can
+        /// therefore flag nodes that would be driven past `target_utilization`.
+        pub per_node_detail: Vec<NodeLoad>,
+
+        /// Whether every node in `per_node_detail` stays at or below `target_utilization`
+        /// and every shard copy has a concrete placement.
+        ///
+        /// Always `true` for [`plan_capacity`] since it has no per-node capacities to
+        /// check against; only [`plan_capacity_nodes`] can actually falsify this.
+        pub feasible: bool,
+
         // --- Inputs echoed for reporting ---
         /// Target maximum disk utilization ratio (e.g. 0.75 = 75%).
         pub target_utilization: f64,
@@ -62,6 +101,8 @@ pub mod planner {
         pub headroom: f64,
         /// Optional relocation buffer per node in GB (defaults to shard size if `None`).
         pub buffer_per_node_gb: Option<f64>,
+        /// Optional stored/raw compression ratio (defaults to `1.0`, i.e. no assumed savings).
+        pub compression_ratio: Option<f64>,
     }
 
     /// Computes an estimated disk capacity plan for an Elasticsearch cluster.
@@ -75,7 +116,12 @@ pub mod planner {
     /// - `primaries` — Total number of primary shards across all indices.
     /// - `replicas` — Number of replicas for each primary shard.
     /// - `shard_size_gb` — Average size of a single shard, in gigabytes (GB).
-    /// - `overhead_merge` — Fractional overhead for Lucene segment merges (e.g. `0.2` = 20%).
+    /// - `compression_ratio` — Optional stored/raw ratio applied to the raw shard data
+    ///   (e.g. `0.5` if compression roughly halves stored size). If `None`, defaults to
+    ///   `1.0` (no assumed savings — compression is not guaranteed to reduce bytes).
+    /// - `overhead_merge` — Fractional transient overhead for a force-merge of the
+    ///   largest index (e.g. `0.2` = 20%). Applied only to one shard's worth of data,
+    ///   since Lucene rewrites a single shard at a time, not the whole cluster.
     /// - `headroom` — Fractional safety margin for disk watermarks and ingestion bursts (e.g. `0.3` = 30%).
     /// - `buffer_per_node_gb` — Optional relocation/rebalancing buffer per node.  
     ///   If `None`, defaults to `shard_size_gb`.
@@ -95,13 +141,15 @@ pub mod planner {
     /// - `target_utilization` ≤ `0.0` or > `1.0`
     /// - `overhead_merge` or `headroom` < `0.0`
     /// - `shard_size_gb` ≤ `0.0`
+    /// - `compression_ratio` is `Some(x)` with `x` ≤ `0.0`
     ///
     /// # Formulas
     ///
     /// ```text
-    /// base = primaries * shard_size_gb * (1 + replicas)
-    /// with_merge = base * (1 + overhead_merge)
-    /// with_headroom = with_merge * (1 + headroom)
+    /// raw_base = primaries * shard_size_gb * (1 + replicas)
+    /// stored_base = raw_base * compression_ratio
+    /// peak_merge = stored_base + (shard_size_gb * compression_ratio * overhead_merge)
+    /// with_headroom = peak_merge * (1 + headroom)
     /// buffer_total = buffer_per_node_gb * nodes
     /// total_cluster = with_headroom + buffer_total
     /// per_node = total_cluster / nodes
@@ -113,10 +161,10 @@ pub mod planner {
     /// ```
     /// use es_disk_planner::{plan_capacity, Plan};
     ///
-    /// let plan = plan_capacity(5, 10, 1, 50.0, 0.20, 0.30, None, 0.75).unwrap();
+    /// let plan = plan_capacity(5, 10, 1, 50.0, None, 0.20, 0.30, None, 0.75).unwrap();
     ///
-    /// assert!((plan.total_cluster - 1810.0).abs() < 1e-6);
-    /// assert!((plan.disk_per_node - 482.7).abs() < 0.1);
+    /// assert!((plan.total_cluster - 1563.0).abs() < 1e-6);
+    /// assert!((plan.disk_per_node - 416.8).abs() < 0.1);
     /// ```
     ///
     /// # Notes
@@ -134,6 +182,7 @@ pub mod planner {
         primaries: u32,
         replicas: u32,
         shard_size_gb: f64,
+        compression_ratio: Option<f64>,
         overhead_merge: f64,
         headroom: f64,
         buffer_per_node_gb: Option<f64>,
@@ -151,18 +200,24 @@ pub mod planner {
         if shard_size_gb <= 0.0 {
             return Err("shard_size_gb must be > 0".into());
         }
+        if compression_ratio.is_some_and(|r| r <= 0.0) {
+            return Err("compression_ratio must be > 0".into());
+        }
 
         let nodes_f = nodes as f64;
         let primaries_f = primaries as f64;
         let replicas_f = replicas as f64;
+        let ratio = compression_ratio.unwrap_or(1.0);
 
         let buf = buffer_per_node_gb.unwrap_or(shard_size_gb);
 
-        let base = primaries_f * shard_size_gb * (1.0 + replicas_f);
+        let raw_base = primaries_f * shard_size_gb * (1.0 + replicas_f);
+
+        let stored_base = raw_base * ratio;
 
-        let with_merge = base * (1.0 + overhead_merge);
+        let peak_merge = stored_base + (shard_size_gb * ratio * overhead_merge);
 
-        let with_headroom = with_merge * (1.0 + headroom);
+        let with_headroom = peak_merge * (1.0 + headroom);
 
         let buffer_total = buf * nodes_f;
 
@@ -173,13 +228,16 @@ pub mod planner {
         let disk_per_node = per_node / target_utilization;
 
         Ok(Plan {
-            base,
-            with_merge,
+            raw_base,
+            stored_base,
+            peak_merge,
             with_headroom,
             buffer_total,
             total_cluster,
             per_node,
             disk_per_node,
+            per_node_detail: Vec::new(),
+            feasible: true,
             target_utilization,
             nodes,
             primaries,
@@ -188,87 +246,258 @@ pub mod planner {
             overhead_merge,
             headroom,
             buffer_per_node_gb,
+            compression_ratio,
         })
     }
 
-    fn fmt_gb(x: f64) -> String {
-        format!("{:.1} GB", x)
+    /// A single node's known physical capacity, for heterogeneous-cluster planning.
+    ///
+    /// Used with [`plan_capacity_nodes`], which (unlike [`plan_capacity`]) does not
+    /// assume every node is identical: each node reports its own `capacity_gb`, and
+    /// the planner checks whether that node would be driven past `target_utilization`.
+    #[derive(Debug, Clone)]
+    pub struct NodeSpec {
+        /// This node's physical disk capacity, in gigabytes (GB, base-10).
+        pub capacity_gb: f64,
+        /// Optional failure-domain label (rack, AZ, etc.) for this node.
+        pub zone: Option<String>,
     }
-    fn fmt_tb(x: f64) -> String {
-        format!("{:.2} TB", x / 1000.0)
+
+    /// The load assigned to a single node by [`plan_capacity_nodes`].
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct NodeLoad {
+        /// This node's physical disk capacity, echoed from the input [`NodeSpec`].
+        pub capacity_gb: f64,
+        /// This node's zone, echoed from the input [`NodeSpec`].
+        pub zone: Option<String>,
+        /// Data this node would carry, derived from its actual share of shard
+        /// copies (via [`plan_placement`](crate::plan_placement)'s max-flow
+        /// assignment) rather than a flat proportion of capacity.
+        ///
+        /// Formula: `copies_on_node * (shard_size_gb * compression_ratio) *
+        /// (with_headroom / stored_base) + buffer_per_node_gb`
+        pub assigned_gb: f64,
+        /// `assigned_gb / capacity_gb`.
+        pub utilization: f64,
+        /// Whether `utilization` exceeds `target_utilization`.
+        pub overloaded: bool,
     }
 
-    impl Display for Plan {
-        fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-            writeln!(f, "=== Elasticsearch Disk Capacity Planner ===")?;
-            writeln!(f, "Nodes: {}", self.nodes)?;
-            writeln!(f, "Primary shards: {}", self.primaries)?;
-            writeln!(f, "Replicas per shard: {}", self.replicas)?;
-            writeln!(
-                f,
-                "Shard size: {} | Overhead merge: {:.0}% | Headroom: {:.0}%",
-                fmt_gb(self.shard_size_gb),
+    /// Computes a capacity plan across nodes with distinct physical capacities.
+    ///
+    /// Unlike [`plan_capacity`], which divides the cluster total evenly across
+    /// identical nodes, this function takes each node's actual `capacity_gb` and
+    /// runs [`plan_placement`](crate::plan_placement) to find a concrete,
+    /// zone-respecting shard-copy assignment, so a cluster of mismatched hardware
+    /// is planned honestly — including catching the case where some nodes would
+    /// be driven past `target_utilization` while others have room to spare,
+    /// which a flat proportional split could never reveal (every node's
+    /// `utilization` would reduce to the same cluster-wide ratio).
+    ///
+    /// # Parameters
+    ///
+    /// - `node_specs` — One entry per data node, with that node's capacity and zone.
+    ///   Must be non-empty.
+    /// - The remaining parameters match [`plan_capacity`].
+    ///
+    /// # Returns
+    ///
+    /// A [`Plan`] whose `per_node_detail` has one [`NodeLoad`] per input node, and
+    /// whose `feasible` is `false` if any node would exceed `target_utilization *
+    /// capacity_gb`, or if [`plan_placement`](crate::plan_placement) can't find a
+    /// concrete assignment for every shard copy. The cluster-level fields
+    /// (`stored_base`, `peak_merge`, ...) are computed exactly as in
+    /// [`plan_capacity`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`plan_capacity`], plus an error if `node_specs` is empty or any
+    /// `capacity_gb` is `<= 0.0`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn plan_capacity_nodes(
+        node_specs: &[NodeSpec],
+        primaries: u32,
+        replicas: u32,
+        shard_size_gb: f64,
+        compression_ratio: Option<f64>,
+        overhead_merge: f64,
+        headroom: f64,
+        buffer_per_node_gb: Option<f64>,
+        target_utilization: f64,
+    ) -> Result<Plan, String> {
+        if node_specs.is_empty() {
+            return Err("node_specs must not be empty".into());
+        }
+        if node_specs.iter().any(|n| n.capacity_gb <= 0.0) {
+            return Err("every node's capacity_gb must be > 0".into());
+        }
+
+        let nodes = node_specs.len() as u32;
+        let mut plan = plan_capacity(
+            nodes,
+            primaries,
+            replicas,
+            shard_size_gb,
+            compression_ratio,
+            overhead_merge,
+            headroom,
+            buffer_per_node_gb,
+            target_utilization,
+        )?;
+
+        let placement = crate::placement::plan_placement(
+            node_specs,
+            primaries,
+            replicas,
+            shard_size_gb,
+            compression_ratio,
+            target_utilization,
+        )?;
+
+        let stored_per_copy = shard_size_gb * compression_ratio.unwrap_or(1.0);
+        // Merge-overhead + headroom inflation, expressed as a ratio so it can be
+        // applied per-copy the same way it's applied to the cluster total.
+        let inflate = if plan.stored_base > 0.0 {
+            plan.with_headroom / plan.stored_base
+        } else {
+            1.0
+        };
+        let buf = buffer_per_node_gb.unwrap_or(shard_size_gb);
+
+        let mut feasible = placement.feasible;
+
+        plan.per_node_detail = node_specs
+            .iter()
+            .zip(&placement.copies_per_node)
+            .map(|(n, &copies)| {
+                let assigned_gb = copies as f64 * stored_per_copy * inflate + buf;
+                let utilization = assigned_gb / n.capacity_gb;
+                let overloaded = utilization > target_utilization;
+                if overloaded {
+                    feasible = false;
+                }
+                NodeLoad {
+                    capacity_gb: n.capacity_gb,
+                    zone: n.zone.clone(),
+                    assigned_gb,
+                    utilization,
+                    overloaded,
+                }
+            })
+            .collect();
+        plan.feasible = feasible;
+
+        Ok(plan)
+    }
+
+    /// Numeric convention for human-readable size formatting.
+    ///
+    /// Elasticsearch disk watermark settings and OS disk-usage tools frequently
+    /// disagree on base-10 vs base-1024, which is exactly the kind of rounding
+    /// that eats into a headroom margin — so callers pick explicitly via
+    /// [`Plan::format_with_units`] rather than relying on [`Plan`]'s [`Display`]
+    /// impl, which always assumes [`Units::Si`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Units {
+        /// Decimal, base-1000 (GB, TB, ...) — matches Elasticsearch's own watermark units.
+        Si,
+        /// Binary, base-1024 (GiB, TiB, ...) — matches most OS disk-usage reporting.
+        Iec,
+    }
+
+    pub(crate) fn fmt_unit(gb: f64, units: Units) -> String {
+        let bytes = (gb * 1e9).round().max(0.0) as u64;
+        // bytesize's `si_unit` flag is named for the *prefix family* (Ki/Mi/Gi),
+        // which is the opposite of decimal SI units, so the bool is inverted here.
+        bytesize::ByteSize::b(bytes).to_string_as(units == Units::Iec)
+    }
+
+    impl Plan {
+        /// Renders this plan the same way [`Display`] does, but picking a single
+        /// best-fit unit per value (via `units`) instead of always printing a
+        /// fixed GB column next to a fixed TB column.
+        pub fn format_with_units(&self, units: Units) -> String {
+            let mut out = String::new();
+            out.push_str("=== Elasticsearch Disk Capacity Planner ===\n");
+            out.push_str(&format!("Nodes: {}\n", self.nodes));
+            out.push_str(&format!("Primary shards: {}\n", self.primaries));
+            out.push_str(&format!("Replicas per shard: {}\n", self.replicas));
+            out.push_str(&format!(
+                "Shard size: {} | Overhead merge: {:.0}% | Headroom: {:.0}%\n",
+                fmt_unit(self.shard_size_gb, units),
                 self.overhead_merge * 100.0,
                 self.headroom * 100.0
-            )?;
-            writeln!(
-                f,
-                "Relocation buffer per node: {}",
-                fmt_gb(self.buffer_per_node_gb.unwrap_or(self.shard_size_gb))
-            )?;
-            writeln!(
-                f,
-                "Target disk utilization: {:.0}%",
+            ));
+            out.push_str(&format!(
+                "Relocation buffer per node: {}\n",
+                fmt_unit(self.buffer_per_node_gb.unwrap_or(self.shard_size_gb), units)
+            ));
+            out.push_str(&format!(
+                "Target disk utilization: {:.0}%\n\n",
                 self.target_utilization * 100.0
-            )?;
-            writeln!(f)?;
-
-            writeln!(
-                f,
-                "Base (primaries+replicas): {} ({})",
-                fmt_gb(self.base),
-                fmt_tb(self.base)
-            )?;
-            writeln!(
-                f,
-                "+ Merge overhead:         {} ({})",
-                fmt_gb(self.with_merge),
-                fmt_tb(self.with_merge)
-            )?;
-            writeln!(
-                f,
-                "+ Headroom:               {} ({})",
-                fmt_gb(self.with_headroom),
-                fmt_tb(self.with_headroom)
-            )?;
-            writeln!(
-                f,
-                "+ Total buffer:           {} ({})",
-                fmt_gb(self.buffer_total),
-                fmt_tb(self.buffer_total)
-            )?;
-            writeln!(
-                f,
-                "= Cluster total:          {} ({})",
-                fmt_gb(self.total_cluster),
-                fmt_tb(self.total_cluster)
-            )?;
-            writeln!(f)?;
-            writeln!(
-                f,
-                "Per node (recommended):   {} ({})",
-                fmt_gb(self.per_node),
-                fmt_tb(self.per_node)
-            )?;
-            writeln!(
-                f,
-                "Disk per node (<~{:.0}%): {} ({})",
+            ));
+
+            out.push_str(&format!(
+                "Base (primaries+replicas): {}\n",
+                fmt_unit(self.stored_base, units)
+            ));
+            out.push_str(&format!(
+                "+ Merge overhead:         {}\n",
+                fmt_unit(self.peak_merge, units)
+            ));
+            out.push_str(&format!(
+                "+ Headroom:               {}\n",
+                fmt_unit(self.with_headroom, units)
+            ));
+            out.push_str(&format!(
+                "+ Total buffer:           {}\n",
+                fmt_unit(self.buffer_total, units)
+            ));
+            out.push_str(&format!(
+                "= Cluster total:          {}\n\n",
+                fmt_unit(self.total_cluster, units)
+            ));
+            out.push_str(&format!(
+                "Per node (recommended):   {}\n",
+                fmt_unit(self.per_node, units)
+            ));
+            out.push_str(&format!(
+                "Disk per node (<~{:.0}%): {}\n",
                 self.target_utilization * 100.0,
-                fmt_gb(self.disk_per_node),
-                fmt_tb(self.disk_per_node)
-            )?;
+                fmt_unit(self.disk_per_node, units)
+            ));
+
+            if !self.per_node_detail.is_empty() {
+                out.push_str(&format!(
+                    "\nFeasible: {}\n",
+                    if self.feasible { "yes" } else { "NO" }
+                ));
+                for (i, n) in self.per_node_detail.iter().enumerate() {
+                    out.push_str(&format!(
+                        "  node[{}]{}: {} / {} ({:.0}%){}\n",
+                        i,
+                        n.zone
+                            .as_deref()
+                            .map(|z| format!(" zone={z}"))
+                            .unwrap_or_default(),
+                        fmt_unit(n.assigned_gb, units),
+                        fmt_unit(n.capacity_gb, units),
+                        n.utilization * 100.0,
+                        if n.overloaded { " OVERLOADED" } else { "" }
+                    ));
+                }
+            }
+
+            out
+        }
+    }
 
-            Ok(())
+    impl Display for Plan {
+        /// Delegates to [`Plan::format_with_units`] with [`Units::Si`], so
+        /// `plan.to_string()`/`println!("{}", plan)` get the same staged report
+        /// as every other caller, just with a sane default unit convention.
+        fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+            write!(f, "{}", self.format_with_units(Units::Si))
         }
     }
 }
@@ -280,30 +509,124 @@ mod tests {
     // Scenario: 5 nodi, 10 primari, 1 replica, shard=50GB, overhead=20%, headroom=30%, buffer=default(=50GB), target=0.75
     #[test]
     fn example_numbers_match() {
-        let p = plan_capacity(5, 10, 1, 50.0, 0.20, 0.30, None, 0.75).unwrap();
-        assert!((p.base - 1000.0).abs() < 1e-6);
-        assert!((p.with_merge - 1200.0).abs() < 1e-6);
-        assert!((p.with_headroom - 1560.0).abs() < 1e-6);
+        let p = plan_capacity(5, 10, 1, 50.0, None, 0.20, 0.30, None, 0.75).unwrap();
+        assert!((p.raw_base - 1000.0).abs() < 1e-6);
+        assert!((p.stored_base - 1000.0).abs() < 1e-6);
+        // peak_merge = stored_base + shard_size_gb*overhead_merge = 1000 + 50*0.2 = 1010
+        assert!((p.peak_merge - 1010.0).abs() < 1e-6);
+        assert!((p.with_headroom - 1313.0).abs() < 1e-6);
         assert!((p.buffer_total - 250.0).abs() < 1e-6);
-        assert!((p.total_cluster - 1810.0).abs() < 1e-6);
-        assert!((p.per_node - 362.0).abs() < 1e-6);
-        assert!((p.disk_per_node - 482.6666667).abs() < 1e-3);
+        assert!((p.total_cluster - 1563.0).abs() < 1e-6);
+        assert!((p.per_node - 312.6).abs() < 1e-6);
+        assert!((p.disk_per_node - 416.8).abs() < 1e-3);
     }
 
     #[test]
     fn rejects_bad_utilization() {
-        assert!(plan_capacity(5, 10, 1, 50.0, 0.2, 0.3, None, 0.0).is_err());
-        assert!(plan_capacity(5, 10, 1, 50.0, 0.2, 0.3, None, 1.01).is_err());
+        assert!(plan_capacity(5, 10, 1, 50.0, None, 0.2, 0.3, None, 0.0).is_err());
+        assert!(plan_capacity(5, 10, 1, 50.0, None, 0.2, 0.3, None, 1.01).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_compression_ratio() {
+        assert!(plan_capacity(5, 10, 1, 50.0, Some(0.0), 0.2, 0.3, None, 0.75).is_err());
+        assert!(plan_capacity(5, 10, 1, 50.0, Some(-0.5), 0.2, 0.3, None, 0.75).is_err());
+    }
+
+    #[test]
+    fn compression_ratio_shrinks_stored_base_but_not_raw_base() {
+        let p = plan_capacity(5, 10, 1, 50.0, Some(0.5), 0.20, 0.30, None, 0.75).unwrap();
+        assert!((p.raw_base - 1000.0).abs() < 1e-6);
+        assert!((p.stored_base - 500.0).abs() < 1e-6);
+        // peak_merge = stored_base + (shard_size_gb*ratio)*overhead_merge = 500 + 25*0.2 = 505
+        assert!((p.peak_merge - 505.0).abs() < 1e-6);
     }
 
     #[test]
     fn custom_buffer() {
-        let p = plan_capacity(3, 6, 1, 40.0, 0.1, 0.2, Some(80.0), 0.8).unwrap();
-        // base = 6*40*(1+1)=480; with_merge=528; with_headroom=633.6; buffer_total=80*3=240; total=873.6
-        assert!((p.total_cluster - 873.6).abs() < 1e-6);
-        // per_node = 291.2; disk_per_node = 291.2/0.8 = 364
-        assert!((p.disk_per_node - 364.0).abs() < 1e-6);
+        let p = plan_capacity(3, 6, 1, 40.0, None, 0.1, 0.2, Some(80.0), 0.8).unwrap();
+        // raw_base=stored_base=480; peak_merge=480+40*0.1=484; with_headroom=484*1.2=580.8;
+        // buffer_total=80*3=240; total=820.8
+        assert!((p.total_cluster - 820.8).abs() < 1e-6);
+        // per_node = 273.6; disk_per_node = 273.6/0.8 = 342
+        assert!((p.disk_per_node - 342.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn heterogeneous_nodes_assignment_tracks_real_placement() {
+        // 3 unzoned nodes, 10 primaries + 1 replica (2 copies/primary, 20 total).
+        // Unlike a flat capacity-proportional split (which would give every node
+        // the same utilization ratio), the copies each node actually receives
+        // from plan_placement's max-flow assignment can vary independently of
+        // its capacity share, so one node can be overloaded while another with
+        // equal capacity is not.
+        let specs = vec![
+            NodeSpec {
+                capacity_gb: 1800.0,
+                zone: None,
+            },
+            NodeSpec {
+                capacity_gb: 600.0,
+                zone: None,
+            },
+            NodeSpec {
+                capacity_gb: 600.0,
+                zone: None,
+            },
+        ];
+        let p = plan_capacity_nodes(&specs, 10, 1, 50.0, None, 0.20, 0.30, None, 0.75).unwrap();
+        assert_eq!(p.per_node_detail.len(), 3);
+        assert!((p.per_node_detail[0].assigned_gb - 706.5).abs() < 1e-6);
+        assert!((p.per_node_detail[1].assigned_gb - 640.85).abs() < 1e-6);
+        assert!((p.per_node_detail[2].assigned_gb - 115.65).abs() < 1e-6);
+        assert!(!p.per_node_detail[0].overloaded);
+        assert!(p.per_node_detail[1].overloaded);
+        assert!(!p.per_node_detail[2].overloaded);
+        // Two equal-capacity nodes (1 and 2) end up with different utilization,
+        // which a pure capacity-proportional split could never produce.
+        assert!((p.per_node_detail[1].utilization - p.per_node_detail[2].utilization).abs() > 0.1);
+        assert!(!p.feasible);
     }
-}
 
-pub use planner::{plan_capacity, Plan};
\ No newline at end of file
+    #[test]
+    fn heterogeneous_nodes_detects_infeasible_total_capacity() {
+        let specs = vec![
+            NodeSpec {
+                capacity_gb: 500.0,
+                zone: None,
+            },
+            NodeSpec {
+                capacity_gb: 200.0,
+                zone: None,
+            },
+        ];
+        let p = plan_capacity_nodes(&specs, 10, 1, 50.0, None, 0.20, 0.30, None, 0.75).unwrap();
+        assert!(!p.feasible);
+        assert!(p.per_node_detail.iter().all(|n| n.overloaded));
+    }
+
+    #[test]
+    fn format_with_units_picks_si_or_iec() {
+        let p = plan_capacity(5, 10, 1, 50.0, None, 0.20, 0.30, None, 0.75).unwrap();
+        let si = p.format_with_units(Units::Si);
+        let iec = p.format_with_units(Units::Iec);
+        assert!(si.contains("GB"));
+        assert!(iec.contains("GiB"));
+    }
+
+    #[test]
+    fn display_delegates_to_format_with_units_si() {
+        let p = plan_capacity(5, 10, 1, 50.0, None, 0.20, 0.30, None, 0.75).unwrap();
+        assert_eq!(p.to_string(), p.format_with_units(Units::Si));
+    }
+
+    #[test]
+    fn heterogeneous_nodes_rejects_empty_or_bad_capacity() {
+        assert!(plan_capacity_nodes(&[], 10, 1, 50.0, None, 0.2, 0.3, None, 0.75).is_err());
+        let bad = vec![NodeSpec {
+            capacity_gb: 0.0,
+            zone: None,
+        }];
+        assert!(plan_capacity_nodes(&bad, 10, 1, 50.0, None, 0.2, 0.3, None, 0.75).is_err());
+    }
+}
\ No newline at end of file
@@ -1,5 +1,50 @@
-use clap::Parser;
-use es_disk_planner::plan_capacity;
+use bytesize::ByteSize;
+use clap::{Parser, ValueEnum};
+use es_disk_planner::{plan_capacity, render, validate_against_disk, OutputFormat, Units};
+use std::path::PathBuf;
+
+/// Numeric convention for human-readable size output, selectable on the CLI.
+///
+/// Mirrors [`Units`], but as a `clap`-friendly enum so `--units si|iec` reads
+/// naturally; converted to the library's [`Units`] before use.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum UnitsArg {
+    /// Decimal, base-1000 (GB, TB, ...).
+    Si,
+    /// Binary, base-1024 (GiB, TiB, ...).
+    Iec,
+}
+
+impl From<UnitsArg> for Units {
+    fn from(u: UnitsArg) -> Self {
+        match u {
+            UnitsArg::Si => Units::Si,
+            UnitsArg::Iec => Units::Iec,
+        }
+    }
+}
+
+/// Output shape, selectable on the CLI. Mirrors [`OutputFormat`]; converted
+/// before use.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum OutputArg {
+    /// The staged, human-oriented report (the default).
+    Text,
+    /// The full plan as pretty-printed JSON.
+    Json,
+    /// The staged breakdown and per-node rows as an aligned column table.
+    Table,
+}
+
+impl From<OutputArg> for OutputFormat {
+    fn from(o: OutputArg) -> Self {
+        match o {
+            OutputArg::Text => OutputFormat::Text,
+            OutputArg::Json => OutputFormat::Json,
+            OutputArg::Table => OutputFormat::Table,
+        }
+    }
+}
 
 /// Command-line arguments for the Elasticsearch Disk Capacity Planner.
 #[derive(Debug, Parser)]
@@ -16,11 +61,17 @@ struct Args {
     #[arg(long, default_value_t = 1)]
     replicas: u32,
 
-    /// Average size of a single shard in gigabytes (base-10 GB).
-    #[arg(long, default_value_t = 50.0)]
-    shard_size_gb: f64,
+    /// Average size of a single shard (e.g. `50GB`, `48GiB`).
+    #[arg(long, default_value = "50GB")]
+    shard_size: ByteSize,
+
+    /// Stored/raw compression ratio (e.g. 0.5 if compression roughly halves stored
+    /// size). Defaults to 1.0: compression isn't guaranteed to reduce bytes.
+    #[arg(long)]
+    compression_ratio: Option<f64>,
 
-    /// Additional temporary space required for Lucene segment merges (fraction, e.g. 0.2 = 20%).
+    /// Additional temporary space required for a force-merge of the largest index
+    /// (fraction of that shard's stored size, e.g. 0.2 = 20%).
     #[arg(long, default_value_t = 0.20)]
     overhead_merge: f64,
 
@@ -28,14 +79,32 @@ struct Args {
     #[arg(long, default_value_t = 0.30)]
     headroom: f64,
 
-    /// Extra buffer per node (in GB) reserved for shard relocation and rebalancing.
-    /// If omitted, defaults to `shard_size_gb`.
+    /// Extra buffer per node (e.g. `1.5TiB`) reserved for shard relocation and
+    /// rebalancing. If omitted, defaults to `shard_size`.
     #[arg(long)]
-    buffer_per_node_gb: Option<f64>,
+    buffer_per_node: Option<ByteSize>,
 
     /// Maximum desired disk utilization ratio per node (e.g. 0.75 = keep usage below ~75%).
     #[arg(long, default_value_t = 0.75)]
     target_utilization: f64,
+
+    /// Validate the plan against the real free disk space at this path (e.g. the
+    /// data node's data directory), failing if the estimate wouldn't fit.
+    #[arg(long)]
+    validate_path: Option<PathBuf>,
+
+    /// Unit convention for the printed plan: decimal (si) or binary (iec).
+    #[arg(long, value_enum, default_value_t = UnitsArg::Si)]
+    units: UnitsArg,
+
+    /// Output format: human-readable text, JSON, or an aligned table.
+    #[arg(long, value_enum, default_value_t = OutputArg::Text)]
+    output: OutputArg,
+}
+
+/// Converts a [`ByteSize`] to decimal gigabytes, the unit [`plan_capacity`] works in.
+fn to_gb(b: ByteSize) -> f64 {
+    b.as_u64() as f64 / 1e9
 }
 
 fn main() {
@@ -45,13 +114,29 @@ fn main() {
         a.nodes,
         a.primaries,
         a.replicas,
-        a.shard_size_gb,
+        to_gb(a.shard_size),
+        a.compression_ratio,
         a.overhead_merge,
         a.headroom,
-        a.buffer_per_node_gb,
+        a.buffer_per_node.map(to_gb),
         a.target_utilization,
     ) {
-        Ok(plan) => println!("{}", plan),
+        Ok(plan) => {
+            match render(&plan, a.output.into(), a.units.into()) {
+                Ok(rendered) => println!("{}", rendered),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(4);
+                }
+            }
+
+            if let Some(path) = &a.validate_path {
+                if let Err(e) = validate_against_disk(&plan, path, None) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(3);
+                }
+            }
+        }
         Err(e) => {
             eprintln!("Error: {}", e);
             std::process::exit(2);
@@ -0,0 +1,146 @@
+//! Pluggable output formatters for a [`Plan`].
+//!
+//! Factors the rendering that used to live solely in `Plan`'s `Display` impl
+//! into named formats, so the CLI's `--output` flag (and, eventually, other
+//! consumers) can pick text, machine-readable JSON, or an aligned table.
+
+use crate::planner::{fmt_unit, Plan, Units};
+
+/// Which shape to render a [`Plan`] into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The same staged, human-oriented report as `Plan`'s `Display` impl.
+    Text,
+    /// The full `Plan` (all intermediate stages and echoed inputs), pretty-printed
+    /// as JSON, for piping into capacity dashboards or CI gates.
+    Json,
+    /// The staged breakdown and per-node rows as an aligned column table.
+    Table,
+}
+
+/// Renders `plan` in the given `format`, using `units` for any human-readable
+/// sizes (ignored for [`OutputFormat::Json`], which reports raw GB floats).
+///
+/// # Errors
+///
+/// Returns an [`Err`] string if JSON serialization fails (it shouldn't, since
+/// [`Plan`] contains no maps with non-string keys or other non-serializable data).
+pub fn render(plan: &Plan, format: OutputFormat, units: Units) -> Result<String, String> {
+    match format {
+        OutputFormat::Text => Ok(plan.format_with_units(units)),
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(plan).map_err(|e| format!("failed to serialize plan: {e}"))
+        }
+        OutputFormat::Table => Ok(render_table(plan, units)),
+    }
+}
+
+fn render_table(plan: &Plan, units: Units) -> String {
+    let rows = vec![
+        vec!["base".to_string(), fmt_unit(plan.stored_base, units)],
+        vec!["+merge".to_string(), fmt_unit(plan.peak_merge, units)],
+        vec!["+headroom".to_string(), fmt_unit(plan.with_headroom, units)],
+        vec!["+buffer".to_string(), fmt_unit(plan.buffer_total, units)],
+        vec!["total".to_string(), fmt_unit(plan.total_cluster, units)],
+        vec!["per_node".to_string(), fmt_unit(plan.per_node, units)],
+        vec![
+            "disk_per_node".to_string(),
+            fmt_unit(plan.disk_per_node, units),
+        ],
+    ];
+
+    let mut out = format_table(&["stage", "size"], &rows);
+
+    if !plan.per_node_detail.is_empty() {
+        out.push('\n');
+        let node_rows: Vec<Vec<String>> = plan
+            .per_node_detail
+            .iter()
+            .enumerate()
+            .map(|(i, n)| {
+                vec![
+                    i.to_string(),
+                    n.zone.clone().unwrap_or_default(),
+                    fmt_unit(n.assigned_gb, units),
+                    fmt_unit(n.capacity_gb, units),
+                    format!("{:.0}%", n.utilization * 100.0),
+                    if n.overloaded { "OVERLOADED" } else { "" }.to_string(),
+                ]
+            })
+            .collect();
+        out.push_str(&format_table(
+            &["node", "zone", "assigned", "capacity", "util", "status"],
+            &node_rows,
+        ));
+    }
+
+    out
+}
+
+/// Renders `headers` and `rows` as a simple aligned column table: every column
+/// is padded to the widest cell in it, columns separated by two spaces.
+fn format_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.len());
+        }
+    }
+
+    let pad_row = |cells: &[String], widths: &[usize]| -> String {
+        cells
+            .iter()
+            .enumerate()
+            .map(|(i, c)| format!("{:<width$}", c, width = widths[i]))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut out = String::new();
+    let header_cells: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+    out.push_str(&pad_row(&header_cells, &widths));
+    out.push('\n');
+    for row in rows {
+        out.push_str(&pad_row(row, &widths));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan_capacity;
+
+    #[test]
+    fn json_round_trips_through_serde() {
+        let plan = plan_capacity(5, 10, 1, 50.0, None, 0.20, 0.30, None, 0.75).unwrap();
+        let json = render(&plan, OutputFormat::Json, Units::Si).unwrap();
+        let parsed: Plan = serde_json::from_str(&json).unwrap();
+        assert!((parsed.total_cluster - plan.total_cluster).abs() < 1e-9);
+    }
+
+    #[test]
+    fn table_output_is_aligned_and_contains_stages() {
+        let plan = plan_capacity(5, 10, 1, 50.0, None, 0.20, 0.30, None, 0.75).unwrap();
+        let table = render(&plan, OutputFormat::Table, Units::Si).unwrap();
+        assert!(table.contains("base"));
+        assert!(table.contains("total"));
+        let size_col = table.lines().next().unwrap().find("size").unwrap();
+        for line in table.lines() {
+            assert_eq!(&line[size_col - 2..size_col], "  ");
+            assert!(!line[size_col..].starts_with(' '));
+        }
+    }
+
+    #[test]
+    fn table_buffer_row_is_just_the_buffer_not_the_running_total() {
+        let plan = plan_capacity(5, 10, 1, 50.0, None, 0.20, 0.30, None, 0.75).unwrap();
+        let table = render(&plan, OutputFormat::Table, Units::Si).unwrap();
+        let buffer_line = table.lines().find(|l| l.starts_with("+buffer")).unwrap();
+        assert!(buffer_line.contains(&fmt_unit(plan.buffer_total, Units::Si)));
+        assert_ne!(plan.buffer_total, plan.total_cluster);
+    }
+}
@@ -0,0 +1,421 @@
+//! Shard-to-node placement feasibility via maximum flow.
+//!
+//! [`plan_capacity`](crate::plan_capacity) only reasons about aggregate load; it
+//! cannot tell you whether a *concrete* assignment of shard copies to nodes
+//! actually exists (e.g. no two copies of the same primary on one node, zone
+//! spread). This module answers that question exactly, by reducing placement to a
+//! max-flow problem and running Edmonds-Karp;
+//! [`plan_capacity_nodes`](crate::plan_capacity_nodes) uses it to derive each
+//! node's load.
+
+use crate::planner::NodeSpec;
+use std::collections::VecDeque;
+
+/// The outcome of [`plan_placement`]: either a concrete copies-per-node layout, or
+/// an explanation of why no valid layout exists.
+#[derive(Debug, Clone)]
+pub struct PlacementResult {
+    /// Whether every shard copy could be placed without violating any constraint.
+    pub feasible: bool,
+    /// Number of shard copies (across all primaries) assigned to each input node,
+    /// in the same order as the `node_specs` slice passed to [`plan_placement`].
+    pub copies_per_node: Vec<u32>,
+    /// When `feasible` is `false`, a description of the bottleneck (which node or
+    /// zone saturated first) that blocked a full assignment.
+    pub bottleneck: Option<String>,
+}
+
+/// Computes a concrete shard-copy-to-node assignment, or explains why none exists.
+///
+/// Builds a bipartite flow network mirroring the constraints of a real placement:
+///
+/// - `source -> primary`: capacity `1 + replicas`, the number of copies of that
+///   primary shard that must be placed somewhere.
+/// - `primary -> zone` (only when `node_specs` span more than one distinct zone
+///   value; a missing zone counts as a zone of its own): capacity
+///   `ceil((1 + replicas) / zone_count)`, so no single zone can take more than its
+///   fair share of one primary's copies.
+/// - `zone -> node` (or `primary -> node` directly, when there are no zones):
+///   capacity `1`, so a node can hold at most one copy of any given primary.
+/// - `node -> sink`: capacity `floor(target_utilization * capacity_gb /
+///   stored_shard_size_gb)`, the number of shard copies that node can physically
+///   hold, where `stored_shard_size_gb = shard_size_gb * compression_ratio` (the
+///   same stored-size accounting [`plan_capacity`](crate::plan_capacity) uses).
+///
+/// If the maximum flow equals `primaries * (1 + replicas)`, every copy of every
+/// primary has a distinct, zone-respecting, capacity-respecting home, and
+/// `copies_per_node` reports how many copies landed on each node. Otherwise
+/// `bottleneck` names the node or zone whose capacity saturated the cut.
+///
+/// # Parameters
+///
+/// - `compression_ratio` — Optional stored/raw ratio applied to `shard_size_gb`
+///   before sizing node capacity, mirroring [`plan_capacity`](crate::plan_capacity).
+///   If `None`, defaults to `1.0` (no assumed savings).
+///
+/// # Errors
+///
+/// Returns an [`Err`] string under the same conditions as
+/// [`plan_capacity_nodes`](crate::plan_capacity_nodes): an empty `node_specs`, or
+/// any `capacity_gb <= 0.0`. Also rejects `shard_size_gb <= 0.0` and a
+/// `compression_ratio` of `Some(x)` with `x <= 0.0`.
+#[allow(clippy::too_many_arguments)]
+pub fn plan_placement(
+    node_specs: &[NodeSpec],
+    primaries: u32,
+    replicas: u32,
+    shard_size_gb: f64,
+    compression_ratio: Option<f64>,
+    target_utilization: f64,
+) -> Result<PlacementResult, String> {
+    if node_specs.is_empty() {
+        return Err("node_specs must not be empty".into());
+    }
+    if node_specs.iter().any(|n| n.capacity_gb <= 0.0) {
+        return Err("every node's capacity_gb must be > 0".into());
+    }
+    if shard_size_gb <= 0.0 {
+        return Err("shard_size_gb must be > 0".into());
+    }
+    if compression_ratio.is_some_and(|r| r <= 0.0) {
+        return Err("compression_ratio must be > 0".into());
+    }
+
+    let stored_shard_size_gb = shard_size_gb * compression_ratio.unwrap_or(1.0);
+    let copies = 1 + replicas as i64;
+    let num_primaries = primaries as usize;
+    let num_nodes = node_specs.len();
+
+    // Zone labels, in first-seen order, so zone indices are stable/deterministic.
+    let mut zones: Vec<Option<String>> = Vec::new();
+    for n in node_specs {
+        if !zones.contains(&n.zone) {
+            zones.push(n.zone.clone());
+        }
+    }
+    let zoned = zones.len() > 1;
+    let zone_count = zones.len().max(1) as i64;
+    let zone_of = |n: &NodeSpec| zones.iter().position(|z| z == &n.zone).unwrap();
+
+    // Layout: 0 = source, then one node per primary, then (if zoned) one node per
+    // (primary, zone) pair, then one node per data node, then the sink.
+    let source = 0usize;
+    let primary_base = 1usize;
+    let zone_base = primary_base + num_primaries;
+    let node_base = if zoned {
+        zone_base + num_primaries * zones.len()
+    } else {
+        zone_base
+    };
+    let sink = node_base + num_nodes;
+    let total_vertices = sink + 1;
+
+    let mut graph = FlowGraph::new(total_vertices);
+    let mut node_sink_edge = vec![0usize; num_nodes];
+
+    for p in 0..num_primaries {
+        graph.add_edge(source, primary_base + p, copies);
+
+        if zoned {
+            let per_zone_cap = ceil_div(copies, zone_count);
+            for (z, _) in zones.iter().enumerate() {
+                graph.add_edge(primary_base + p, zone_base + p * zones.len() + z, per_zone_cap);
+            }
+            for (i, n) in node_specs.iter().enumerate() {
+                let z = zone_of(n);
+                graph.add_edge(zone_base + p * zones.len() + z, node_base + i, 1);
+            }
+        } else {
+            for i in 0..num_nodes {
+                graph.add_edge(primary_base + p, node_base + i, 1);
+            }
+        }
+    }
+
+    for (i, n) in node_specs.iter().enumerate() {
+        let capacity_copies =
+            ((target_utilization * n.capacity_gb) / stored_shard_size_gb).floor() as i64;
+        node_sink_edge[i] = graph.add_edge(node_base + i, sink, capacity_copies.max(0));
+    }
+
+    let max_flow = graph.max_flow(source, sink);
+    let required = copies * num_primaries as i64;
+    let feasible = max_flow == required;
+
+    let copies_per_node = (0..num_nodes)
+        .map(|i| graph.flow_on(node_sink_edge[i]) as u32)
+        .collect();
+
+    let bottleneck = if feasible {
+        None
+    } else {
+        let zone_layer = zoned.then_some((zone_base, zones.as_slice()));
+        Some(graph.describe_cut(source, node_base, num_nodes, zone_layer))
+    };
+
+    Ok(PlacementResult {
+        feasible,
+        copies_per_node,
+        bottleneck,
+    })
+}
+
+fn ceil_div(a: i64, b: i64) -> i64 {
+    (a + b - 1) / b
+}
+
+/// A minimal adjacency-list max-flow graph, solved with Edmonds-Karp (BFS
+/// augmenting paths). Edges are stored in forward/backward pairs so residual
+/// capacity can be read directly off the reverse edge.
+struct FlowGraph {
+    adj: Vec<Vec<usize>>,
+    to: Vec<usize>,
+    cap: Vec<i64>,
+}
+
+impl FlowGraph {
+    fn new(n: usize) -> Self {
+        FlowGraph {
+            adj: vec![Vec::new(); n],
+            to: Vec::new(),
+            cap: Vec::new(),
+        }
+    }
+
+    /// Adds a `u -> v` edge with the given capacity (plus its zero-capacity
+    /// reverse edge) and returns the forward edge's index.
+    fn add_edge(&mut self, u: usize, v: usize, capacity: i64) -> usize {
+        let forward = self.to.len();
+        self.to.push(v);
+        self.cap.push(capacity);
+        self.adj[u].push(forward);
+
+        let backward = self.to.len();
+        self.to.push(u);
+        self.cap.push(0);
+        self.adj[v].push(backward);
+
+        forward
+    }
+
+    fn flow_on(&self, edge: usize) -> i64 {
+        // The edge's original capacity minus what remains is the flow it carries;
+        // equivalently, the flow pushed back on its reverse edge.
+        self.cap[edge ^ 1]
+    }
+
+    fn max_flow(&mut self, source: usize, sink: usize) -> i64 {
+        let mut total = 0;
+        while let Some((path, bottleneck)) = self.find_augmenting_path(source, sink) {
+            for &edge in &path {
+                self.cap[edge] -= bottleneck;
+                self.cap[edge ^ 1] += bottleneck;
+            }
+            total += bottleneck;
+        }
+        total
+    }
+
+    fn find_augmenting_path(&self, source: usize, sink: usize) -> Option<(Vec<usize>, i64)> {
+        let mut prev_edge: Vec<Option<usize>> = vec![None; self.adj.len()];
+        let mut visited = vec![false; self.adj.len()];
+        visited[source] = true;
+        let mut queue = VecDeque::new();
+        queue.push_back(source);
+
+        while let Some(u) = queue.pop_front() {
+            if u == sink {
+                break;
+            }
+            for &edge in &self.adj[u] {
+                let v = self.to[edge];
+                if !visited[v] && self.cap[edge] > 0 {
+                    visited[v] = true;
+                    prev_edge[v] = Some(edge);
+                    queue.push_back(v);
+                }
+            }
+        }
+
+        if !visited[sink] {
+            return None;
+        }
+
+        let mut path = Vec::new();
+        let mut bottleneck = i64::MAX;
+        let mut v = sink;
+        while let Some(edge) = prev_edge[v] {
+            bottleneck = bottleneck.min(self.cap[edge]);
+            path.push(edge);
+            v = self.to[edge ^ 1];
+        }
+        path.reverse();
+        Some((path, bottleneck))
+    }
+
+    /// Finds the set of vertices reachable from `source` in the residual graph
+    /// (the source side of a minimum cut) and describes which data nodes, or
+    /// zones, on the cut boundary are the bottleneck.
+    ///
+    /// `zone_layer` is `Some((zone_base, zones))` when the placement is zoned:
+    /// `zone_base` is the first zone-layer vertex (as laid out in
+    /// [`plan_placement`]'s `(primary, zone)` grid) and `zones` are the zone
+    /// labels, in the same order used to compute each vertex's zone index.
+    fn describe_cut(
+        &self,
+        source: usize,
+        node_base: usize,
+        num_nodes: usize,
+        zone_layer: Option<(usize, &[Option<String>])>,
+    ) -> String {
+        let mut visited = vec![false; self.adj.len()];
+        visited[source] = true;
+        let mut queue = VecDeque::new();
+        queue.push_back(source);
+        while let Some(u) = queue.pop_front() {
+            for &edge in &self.adj[u] {
+                let v = self.to[edge];
+                if !visited[v] && self.cap[edge] > 0 {
+                    visited[v] = true;
+                    queue.push_back(v);
+                }
+            }
+        }
+
+        let saturated_nodes: Vec<usize> = (0..num_nodes)
+            .filter(|&i| visited[node_base + i])
+            .collect();
+
+        let saturated_zones: Vec<&str> = zone_layer
+            .map(|(zone_base, zones)| {
+                let num_zone_vertices = node_base - zone_base;
+                (0..num_zone_vertices)
+                    .filter(|&v| visited[zone_base + v])
+                    .map(|v| v % zones.len())
+                    .collect::<std::collections::BTreeSet<_>>()
+                    .into_iter()
+                    .map(|z| zones[z].as_deref().unwrap_or("<no zone>"))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        match (saturated_nodes.is_empty(), saturated_zones.is_empty()) {
+            (true, true) => "no feasible layout: shard replication/zone constraints cannot be \
+                              satisfied even with unlimited disk"
+                .to_string(),
+            (false, true) => format!(
+                "no feasible layout: node(s) {:?} are full (their target-utilization disk \
+                 budget is the bottleneck)",
+                saturated_nodes
+            ),
+            (true, false) => format!(
+                "no feasible layout: zone(s) {:?} are full (they have too few nodes to take \
+                 their per-zone share of a primary's copies, one copy per node)",
+                saturated_zones
+            ),
+            (false, false) => format!(
+                "no feasible layout: node(s) {:?} and zone(s) {:?} are full (node disk \
+                 budget is saturated and the zones have too few nodes for their share)",
+                saturated_nodes, saturated_zones
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(capacity_gb: f64, zone: Option<&str>) -> NodeSpec {
+        NodeSpec {
+            capacity_gb,
+            zone: zone.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn feasible_layout_respects_distinct_node_constraint() {
+        // 2 primaries, 1 replica each (2 copies/primary), 3 nodes each able to hold
+        // plenty of shards: every copy should land on a distinct node per primary.
+        let nodes = vec![node(1000.0, None), node(1000.0, None), node(1000.0, None)];
+        let result = plan_placement(&nodes, 2, 1, 10.0, None, 0.75).unwrap();
+        assert!(result.feasible);
+        assert_eq!(result.copies_per_node.iter().sum::<u32>(), 4);
+    }
+
+    #[test]
+    fn infeasible_when_too_few_nodes_for_replica_count() {
+        // 3 copies per primary (1 primary + 2 replicas) but only 2 nodes: a single
+        // node can't hold two copies of the same primary, so no layout exists
+        // regardless of free disk.
+        let nodes = vec![node(1000.0, None), node(1000.0, None)];
+        let result = plan_placement(&nodes, 1, 2, 10.0, None, 0.75).unwrap();
+        assert!(!result.feasible);
+        assert!(result.bottleneck.is_some());
+    }
+
+    #[test]
+    fn infeasible_when_disk_too_small() {
+        let nodes = vec![node(1.0, None), node(1.0, None)];
+        let result = plan_placement(&nodes, 5, 1, 10.0, None, 0.75).unwrap();
+        assert!(!result.feasible);
+        assert!(result.bottleneck.unwrap().contains("full"));
+    }
+
+    #[test]
+    fn zone_constraint_limits_copies_per_zone() {
+        // 1 primary, 2 replicas (3 copies), zone z1 with 2 nodes and zone z2 with
+        // 1 node: zone cap is ceil(3/2) = 2, so z1 takes at most 2 copies and the
+        // 3rd copy has to land in z2.
+        let nodes = vec![
+            node(1000.0, Some("z1")),
+            node(1000.0, Some("z1")),
+            node(1000.0, Some("z2")),
+        ];
+        let result = plan_placement(&nodes, 1, 2, 10.0, None, 0.75).unwrap();
+        assert!(result.feasible);
+        // All 3 copies placed, z2's single node can only take 1 (zone cap = 2, node cap = 1).
+        assert_eq!(result.copies_per_node[2], 1);
+        assert_eq!(result.copies_per_node[0] + result.copies_per_node[1], 2);
+    }
+
+    #[test]
+    fn bottleneck_names_the_saturated_zone() {
+        // 3 zones of 1 node each, 1 primary + 4 replicas (5 copies): zone cap is
+        // ceil(5/3) = 2 but each zone only has 1 node, so no zone can ever take
+        // its full share and the bottleneck is the zone layer, not raw disk.
+        let nodes = vec![
+            node(1000.0, Some("z1")),
+            node(1000.0, Some("z2")),
+            node(1000.0, Some("z3")),
+        ];
+        let result = plan_placement(&nodes, 1, 4, 10.0, None, 0.75).unwrap();
+        assert!(!result.feasible);
+        let bottleneck = result.bottleneck.unwrap();
+        assert!(bottleneck.contains("zone"));
+        assert!(bottleneck.contains("z1") || bottleneck.contains("z2") || bottleneck.contains("z3"));
+    }
+
+    #[test]
+    fn rejects_empty_nodes() {
+        assert!(plan_placement(&[], 1, 1, 10.0, None, 0.75).is_err());
+    }
+
+    #[test]
+    fn compression_ratio_lets_more_copies_fit() {
+        // 1 node, capacity 100GB, shard 10GB, target 1.0: uncompressed fits 10
+        // copies; halving the stored size via compression_ratio should roughly
+        // double how many copies the node's capacity edge allows.
+        let nodes = vec![node(100.0, None)];
+        let uncompressed = plan_placement(&nodes, 10, 0, 10.0, None, 1.0).unwrap();
+        let compressed = plan_placement(&nodes, 20, 0, 10.0, Some(0.5), 1.0).unwrap();
+        assert_eq!(uncompressed.copies_per_node[0], 10);
+        assert_eq!(compressed.copies_per_node[0], 20);
+    }
+
+    #[test]
+    fn rejects_bad_compression_ratio() {
+        let nodes = vec![node(100.0, None)];
+        assert!(plan_placement(&nodes, 1, 1, 10.0, Some(0.0), 0.75).is_err());
+    }
+}